@@ -0,0 +1,807 @@
+//! mgindb-cli: an interactive shell for MginDB built on the Rust client.
+//!
+//! ```text
+//! mgindb-cli [-h host] [-p port] [-u username] [-a password] [--tls] [--url mgindb://...]
+//!            [--eval "SET foo bar"]... [--file commands.txt]
+//! mgindb-cli [connection options] monitor
+//! mgindb-cli [connection options] bench [-c clients] [-n requests] [-d bytes] [-P depth]
+//!            [-r keyspace] [--mix set=50,get=40,query=10]
+//! mgindb-cli [connection options] migrate --to mgindb://... [--pattern PATTERN] [--concurrency N]
+//!            [--dry-run]
+//! ```
+//!
+//! With --eval or --file the commands run in order without a prompt, each reply is printed on
+//! its own line, and the first failing command stops the run with exit code 1.
+//!
+//! `monitor` prints every command the server executes until interrupted.
+//!
+//! `bench` runs a SET/GET/QUERY workload over a connection pool and reports throughput and
+//! latency percentiles. It writes under the `mgindb_bench` key and deletes it when done.
+//!
+//! `migrate` copies the keys matching a pattern (default `*`) from the connected server to the
+//! one given with --to. With --dry-run it only lists the keys it would copy.
+
+use mgindb::blocking::Client;
+use mgindb::{MginDBClient, MginDBClientBuilder, MginDBPool, MginError, Migrator, RawCommand, Response, TlsConfig};
+use rustyline::completion::{Completer, Pair};
+use rustyline::error::ReadlineError;
+use rustyline::highlight::Highlighter;
+use rustyline::hint::Hinter;
+use rustyline::history::DefaultHistory;
+use rustyline::validate::Validator;
+use rustyline::{Context, Editor, Helper};
+use std::path::PathBuf;
+use std::process::ExitCode;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+// Server commands, as dispatched by the server's command table
+const COMMANDS: &[&str] = &[
+    "BACKUP", "CHECKUPDATE", "CONFIG", "COUNT", "DECR", "DEL", "FLUSHALL", "FLUSHCACHE", "INCR", "INDICES", "KEYS",
+    "QUERY", "RENAME", "ROLLBACK", "SCHEDULE", "SERVERSTOP", "SET", "SUB", "SUBLIST", "UNSUB",
+];
+const SHELL_COMMANDS: &[&str] = &["clear", "exit", "help"];
+
+struct Options {
+    builder: MginDBClientBuilder,
+    batch: Vec<BatchSource>,
+    monitor: bool,
+    bench: Option<BenchOptions>,
+    migrate: Option<MigrateOptions>,
+}
+
+enum BatchSource {
+    Eval(String),
+    // "-" reads from stdin
+    File(String),
+}
+
+fn parse_args(mut args: impl Iterator<Item = String>) -> Result<Options, String> {
+    let mut builder = MginDBClient::builder();
+    let mut username = String::new();
+    let mut password = String::new();
+    let mut batch = Vec::new();
+    let mut monitor = false;
+    let mut bench = false;
+    let mut bench_options = BenchOptions::default();
+    let mut bench_flag_seen = false;
+    let mut migrate = false;
+    let mut migrate_options = MigrateOptions::default();
+    let mut migrate_flag_seen = false;
+
+    while let Some(arg) = args.next() {
+        let mut value = |name: &str| args.next().ok_or_else(|| format!("{} expects a value", name));
+        match arg.as_str() {
+            "-h" | "--host" => builder = builder.host(&value(&arg)?),
+            "-p" | "--port" => {
+                let port = value(&arg)?;
+                builder = builder.port(port.parse().map_err(|_| format!("invalid port '{}'", port))?);
+            }
+            "-u" | "--user" => username = value(&arg)?,
+            "-a" | "--password" => password = value(&arg)?,
+            "--tls" => builder = builder.tls(TlsConfig::default()),
+            "--url" => builder = MginDBClientBuilder::from_url(&value(&arg)?).map_err(|e| e.to_string())?,
+            "-e" | "--eval" => batch.push(BatchSource::Eval(value(&arg)?)),
+            "-f" | "--file" => batch.push(BatchSource::File(value(&arg)?)),
+            "monitor" => monitor = true,
+            "bench" => bench = true,
+            "-c" | "--clients" => {
+                bench_options.clients = parse_count(&arg, &value(&arg)?)? as usize;
+                bench_flag_seen = true;
+            }
+            "-n" | "--requests" => {
+                bench_options.requests = parse_count(&arg, &value(&arg)?)?;
+                bench_flag_seen = true;
+            }
+            "-d" | "--size" => {
+                bench_options.payload_size = parse_count(&arg, &value(&arg)?)? as usize;
+                bench_flag_seen = true;
+            }
+            "-P" | "--pipeline" => {
+                bench_options.pipeline = parse_count(&arg, &value(&arg)?)? as usize;
+                bench_flag_seen = true;
+            }
+            "-r" | "--keyspace" => {
+                bench_options.keyspace = parse_count(&arg, &value(&arg)?)?;
+                bench_flag_seen = true;
+            }
+            "--mix" => {
+                bench_options.mix = parse_mix(&value(&arg)?)?;
+                bench_flag_seen = true;
+            }
+            "migrate" => migrate = true,
+            "--to" => {
+                migrate_options.destination =
+                    Some(MginDBClientBuilder::from_url(&value(&arg)?).map_err(|e| e.to_string())?);
+                migrate_flag_seen = true;
+            }
+            "--pattern" => {
+                migrate_options.pattern = value(&arg)?;
+                migrate_flag_seen = true;
+            }
+            "--concurrency" => {
+                migrate_options.concurrency = parse_count(&arg, &value(&arg)?)? as usize;
+                migrate_flag_seen = true;
+            }
+            "--dry-run" => {
+                migrate_options.dry_run = true;
+                migrate_flag_seen = true;
+            }
+            "--help" => return Err(usage()),
+            other => return Err(format!("unknown argument '{}'\n{}", other, usage())),
+        }
+    }
+
+    if !username.is_empty() || !password.is_empty() {
+        builder = builder.auth(&username, &password);
+    }
+    if monitor && !batch.is_empty() {
+        return Err("monitor cannot be combined with --eval or --file".to_string());
+    }
+    if bench && (monitor || !batch.is_empty()) {
+        return Err("bench cannot be combined with monitor, --eval or --file".to_string());
+    }
+    if bench_flag_seen && !bench {
+        return Err("-c, -n, -d, -P, -r and --mix only apply to bench".to_string());
+    }
+    if migrate && (bench || monitor || !batch.is_empty()) {
+        return Err("migrate cannot be combined with bench, monitor, --eval or --file".to_string());
+    }
+    if migrate_flag_seen && !migrate {
+        return Err("--to, --pattern, --concurrency and --dry-run only apply to migrate".to_string());
+    }
+    if migrate && migrate_options.destination.is_none() {
+        return Err("migrate needs a destination: --to mgindb://...".to_string());
+    }
+    let bench = bench.then_some(bench_options);
+    let migrate = migrate.then_some(migrate_options);
+    Ok(Options { builder, batch, monitor, bench, migrate })
+}
+
+// A positive integer option
+fn parse_count(name: &str, value: &str) -> Result<u64, String> {
+    match value.parse::<u64>() {
+        Ok(count) if count > 0 => Ok(count),
+        _ => Err(format!("{} expects a positive integer, got '{}'", name, value)),
+    }
+}
+
+// "set=50,get=40,query=10"; weights are relative, not percentages
+fn parse_mix(value: &str) -> Result<Vec<(BenchOp, u32)>, String> {
+    let mut mix = Vec::new();
+    for part in value.split(',') {
+        let (name, weight) = part.split_once('=').ok_or_else(|| format!("invalid mix entry '{}'", part))?;
+        let op = match name.trim().to_ascii_lowercase().as_str() {
+            "set" => BenchOp::Set,
+            "get" => BenchOp::Get,
+            "query" => BenchOp::Query,
+            other => return Err(format!("unknown mix operation '{}', expected set, get or query", other)),
+        };
+        let weight = weight.trim().parse().map_err(|_| format!("invalid mix weight '{}'", weight))?;
+        mix.push((op, weight));
+    }
+    if mix.iter().all(|(_, weight)| *weight == 0) {
+        return Err("--mix needs at least one non-zero weight".to_string());
+    }
+    Ok(mix)
+}
+
+fn usage() -> String {
+    concat!(
+        "usage: mgindb-cli [-h host] [-p port] [-u username] [-a password] [--tls] [--url mgindb://...]\n",
+        "                  [-e|--eval COMMAND]... [-f|--file PATH|-] [monitor]\n",
+        "       mgindb-cli [connection options] bench [-c clients] [-n requests] [-d bytes] [-P depth]\n",
+        "                  [-r keyspace] [--mix set=50,get=40,query=10]\n",
+        "       mgindb-cli [connection options] migrate --to mgindb://... [--pattern PATTERN] [--concurrency N]\n",
+        "                  [--dry-run]",
+    )
+    .to_string()
+}
+
+fn main() -> ExitCode {
+    let options = match parse_args(std::env::args().skip(1)) {
+        Ok(options) => options,
+        Err(message) => {
+            eprintln!("{}", message);
+            return ExitCode::from(2);
+        }
+    };
+
+    if let Some(bench) = options.bench {
+        return run_bench(options.builder, bench);
+    }
+    if let Some(migrate) = options.migrate {
+        return run_migrate(options.builder, migrate);
+    }
+
+    let interactive = options.batch.is_empty() && !options.monitor;
+    if interactive {
+        println!("{}", yellow("Connecting to server..."));
+    }
+    let client = match Client::from_builder(options.builder) {
+        Ok(client) => client,
+        Err(e) => {
+            eprintln!("{}", red(&format!("Could not connect: {}", e)));
+            return ExitCode::FAILURE;
+        }
+    };
+
+    if options.monitor {
+        return run_monitor(&client);
+    }
+    if !interactive {
+        let status = run_batch(&client, &options.batch);
+        let _ = client.close();
+        return status;
+    }
+
+    match repl(&client) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("{}", red(&e.to_string()));
+            ExitCode::FAILURE
+        }
+    }
+}
+
+fn repl(client: &Client) -> Result<(), ReadlineError> {
+    let mut editor: Editor<CliHelper, DefaultHistory> = Editor::new()?;
+    editor.set_helper(Some(CliHelper));
+    let history = history_path();
+    if let Some(history) = &history {
+        let _ = editor.load_history(history);
+    }
+
+    println!();
+    println!("MginDB CLI");
+    println!("{}", cyan("Documentation --> https://mgindb.com/documentation.html"));
+    println!("{}", magenta("Type 'help' for commands, 'exit' to quit, 'clear' to clear screen."));
+    println!();
+
+    loop {
+        let line = match editor.readline("MginDB> ") {
+            Ok(line) => line,
+            Err(ReadlineError::Interrupted) => continue,
+            Err(ReadlineError::Eof) => break,
+            Err(e) => return Err(e),
+        };
+        let line = line.trim();
+        if line.is_empty() {
+            continue;
+        }
+        let _ = editor.add_history_entry(line);
+
+        match line.to_ascii_lowercase().as_str() {
+            "exit" | "quit" => break,
+            "clear" => {
+                print!("\x1b[2J\x1b[H");
+                continue;
+            }
+            "help" => {
+                println!("{}", COMMANDS.join(" "));
+                continue;
+            }
+            _ => {}
+        }
+
+        print_result(client.execute(RawCommand::new(line)));
+        if !client.is_connected() {
+            println!("{}", yellow("Connection lost, reconnecting..."));
+        }
+    }
+
+    if let Some(history) = &history {
+        let _ = editor.save_history(history);
+    }
+    let _ = client.close();
+    println!("{}", yellow("Exiting CLI."));
+    Ok(())
+}
+
+// Runs every command in order, stopping at the first failure
+fn run_batch(client: &Client, sources: &[BatchSource]) -> ExitCode {
+    for source in sources {
+        let (origin, text) = match source {
+            BatchSource::Eval(command) => ("--eval".to_string(), command.clone()),
+            BatchSource::File(path) => {
+                let text = if path == "-" {
+                    std::io::read_to_string(std::io::stdin())
+                } else {
+                    std::fs::read_to_string(path)
+                };
+                match text {
+                    Ok(text) => (path.clone(), text),
+                    Err(e) => {
+                        eprintln!("{}: {}", path, e);
+                        return ExitCode::FAILURE;
+                    }
+                }
+            }
+        };
+
+        for (number, line) in text.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            match describe(client.execute(RawCommand::new(line))) {
+                Ok(reply) => println!("{}", reply),
+                Err(message) => {
+                    eprintln!("{}:{}: {}: {}", origin, number + 1, line, message);
+                    return ExitCode::FAILURE;
+                }
+            }
+        }
+    }
+    ExitCode::SUCCESS
+}
+
+fn run_monitor(client: &Client) -> ExitCode {
+    let monitor = match client.monitor() {
+        Ok(monitor) => monitor,
+        Err(e) => {
+            eprintln!("{}", red(&format!("MONITOR failed: {}", e)));
+            return ExitCode::FAILURE;
+        }
+    };
+    eprintln!("{}", yellow("Monitoring commands, press Ctrl-C to stop."));
+    for event in monitor {
+        println!("{} [{}] {}", format_timestamp(event.received_at), event.origin, event.command);
+    }
+    // The stream only ends when the connection is gone for good
+    eprintln!("{}", red("Connection closed."));
+    ExitCode::FAILURE
+}
+
+struct MigrateOptions {
+    destination: Option<MginDBClientBuilder>,
+    pattern: String,
+    concurrency: usize,
+    dry_run: bool,
+}
+
+impl Default for MigrateOptions {
+    fn default() -> Self {
+        MigrateOptions {
+            destination: None,
+            pattern: "*".to_string(),
+            concurrency: 4,
+            dry_run: false,
+        }
+    }
+}
+
+fn run_migrate(source: MginDBClientBuilder, options: MigrateOptions) -> ExitCode {
+    let runtime = match tokio::runtime::Runtime::new() {
+        Ok(runtime) => runtime,
+        Err(e) => {
+            eprintln!("{}", red(&format!("Could not start runtime: {}", e)));
+            return ExitCode::FAILURE;
+        }
+    };
+    runtime.block_on(migrate(source, options))
+}
+
+async fn migrate(source: MginDBClientBuilder, options: MigrateOptions) -> ExitCode {
+    let Some(destination) = options.destination else {
+        return ExitCode::from(2);
+    };
+    let (source, destination) = match tokio::try_join!(source.connect(), destination.connect()) {
+        Ok(clients) => clients,
+        Err(e) => {
+            eprintln!("{}", red(&format!("Could not connect: {}", e)));
+            return ExitCode::FAILURE;
+        }
+    };
+
+    let started = Instant::now();
+    let result = Migrator::new(&source, &destination)
+        .pattern(&options.pattern)
+        .concurrency(options.concurrency)
+        .dry_run(options.dry_run)
+        .run()
+        .await;
+    let _ = tokio::join!(source.close(), destination.close());
+
+    let report = match result {
+        Ok(report) => report,
+        Err(e) => {
+            eprintln!("{}", red(&format!("Migration failed: {}", e)));
+            return ExitCode::FAILURE;
+        }
+    };
+    if options.dry_run {
+        for key in &report.keys {
+            println!("{}", key);
+        }
+        println!(
+            "{}",
+            green(&format!("Would copy {} of {} keys ({} bytes)", report.copied, report.matched, report.bytes))
+        );
+    } else {
+        println!(
+            "{}",
+            green(&format!(
+                "Copied {} of {} keys ({} bytes) in {:.2} seconds",
+                report.copied,
+                report.matched,
+                report.bytes,
+                started.elapsed().as_secs_f64()
+            ))
+        );
+    }
+    ExitCode::SUCCESS
+}
+
+// The benchmark writes everything under this key and deletes it afterwards
+const BENCH_ROOT: &str = "mgindb_bench";
+
+struct BenchOptions {
+    clients: usize,
+    requests: u64,
+    payload_size: usize,
+    pipeline: usize,
+    keyspace: u64,
+    mix: Vec<(BenchOp, u32)>,
+}
+
+impl Default for BenchOptions {
+    fn default() -> Self {
+        BenchOptions {
+            clients: 50,
+            requests: 100_000,
+            payload_size: 16,
+            pipeline: 1,
+            keyspace: 10_000,
+            mix: vec![(BenchOp::Set, 50), (BenchOp::Get, 40), (BenchOp::Query, 10)],
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum BenchOp {
+    Set,
+    Get,
+    Query,
+}
+
+impl BenchOp {
+    const ALL: [BenchOp; 3] = [BenchOp::Set, BenchOp::Get, BenchOp::Query];
+
+    fn name(self) -> &'static str {
+        match self {
+            BenchOp::Set => "SET",
+            BenchOp::Get => "GET",
+            BenchOp::Query => "QUERY",
+        }
+    }
+}
+
+// Latencies of one operation type, in microseconds
+#[derive(Default)]
+struct BenchSamples {
+    latencies: Vec<u64>,
+    errors: u64,
+}
+
+fn run_bench(builder: MginDBClientBuilder, options: BenchOptions) -> ExitCode {
+    let runtime = match tokio::runtime::Runtime::new() {
+        Ok(runtime) => runtime,
+        Err(e) => {
+            eprintln!("{}", red(&format!("Could not start runtime: {}", e)));
+            return ExitCode::FAILURE;
+        }
+    };
+    runtime.block_on(bench(builder, options))
+}
+
+async fn bench(builder: MginDBClientBuilder, options: BenchOptions) -> ExitCode {
+    let pool = match MginDBPool::builder().client(builder).size(options.clients).connect().await {
+        Ok(pool) => Arc::new(pool),
+        Err(e) => {
+            eprintln!("{}", red(&format!("Could not connect: {}", e)));
+            return ExitCode::FAILURE;
+        }
+    };
+
+    let payload = Arc::new("x".repeat(options.payload_size));
+    let mix = Arc::new(options.mix.clone());
+    let remaining = Arc::new(AtomicU64::new(options.requests));
+    eprintln!(
+        "{}",
+        yellow(&format!(
+            "Running {} requests, {} clients, {} byte payload, pipeline depth {}...",
+            options.requests, options.clients, options.payload_size, options.pipeline
+        ))
+    );
+
+    let started = Instant::now();
+    let workers: Vec<_> = (0..options.clients)
+        .map(|worker| {
+            let pool = pool.clone();
+            let payload = payload.clone();
+            let mix = mix.clone();
+            let remaining = remaining.clone();
+            let (depth, keyspace) = (options.pipeline as u64, options.keyspace);
+            tokio::spawn(async move {
+                let mut rng = Rng::new(worker as u64);
+                let mut samples: [BenchSamples; 3] = Default::default();
+                loop {
+                    // Claim up to `depth` requests from the shared budget
+                    let claimed = remaining
+                        .fetch_update(Ordering::Relaxed, Ordering::Relaxed, |left| left.checked_sub(left.min(depth)))
+                        .map(|left| left.min(depth))
+                        .unwrap_or(0);
+                    if claimed == 0 {
+                        break;
+                    }
+
+                    let ops: Vec<BenchOp> = (0..claimed).map(|_| pick_op(&mix, &mut rng)).collect();
+                    let mut pipeline = pool.get().pipeline();
+                    for op in &ops {
+                        let key = format!("{}:k{}", BENCH_ROOT, rng.next() % keyspace);
+                        pipeline = match op {
+                            BenchOp::Set => pipeline.set(&key, &payload),
+                            BenchOp::Get => pipeline.query(&key),
+                            BenchOp::Query => pipeline.cmd(&format!("QUERY {} LIMIT(0,10)", BENCH_ROOT)),
+                        };
+                    }
+
+                    let sent = Instant::now();
+                    let result = pipeline.execute().await;
+                    // Every command in a pipelined batch shares the batch's round trip
+                    let elapsed = sent.elapsed().as_micros() as u64;
+                    match result {
+                        Ok(responses) => {
+                            for (op, response) in ops.iter().zip(responses) {
+                                let samples = &mut samples[*op as usize];
+                                match response {
+                                    Response::Error { .. } => samples.errors += 1,
+                                    _ => samples.latencies.push(elapsed),
+                                }
+                            }
+                        }
+                        Err(_) => ops.iter().for_each(|op| samples[*op as usize].errors += 1),
+                    }
+                }
+                samples
+            })
+        })
+        .collect();
+
+    let mut totals: [BenchSamples; 3] = Default::default();
+    for worker in workers {
+        if let Ok(samples) = worker.await {
+            for (total, samples) in totals.iter_mut().zip(samples) {
+                total.latencies.extend(samples.latencies);
+                total.errors += samples.errors;
+            }
+        }
+    }
+    let elapsed = started.elapsed();
+
+    if let Err(e) = pool.delete(BENCH_ROOT).await {
+        eprintln!("{}", yellow(&format!("Could not delete {}: {}", BENCH_ROOT, e)));
+    }
+    drop(pool);
+
+    print_bench_report(&options, &mut totals, elapsed);
+    if totals.iter().any(|samples| samples.errors > 0) {
+        ExitCode::FAILURE
+    } else {
+        ExitCode::SUCCESS
+    }
+}
+
+fn print_bench_report(options: &BenchOptions, totals: &mut [BenchSamples; 3], elapsed: Duration) {
+    let seconds = elapsed.as_secs_f64().max(f64::EPSILON);
+    let completed: usize = totals.iter().map(|samples| samples.latencies.len()).sum();
+    let errors: u64 = totals.iter().map(|samples| samples.errors).sum();
+
+    println!();
+    println!(
+        "{} requests completed in {:.2} seconds ({} errors)",
+        completed as u64 + errors,
+        elapsed.as_secs_f64(),
+        errors
+    );
+    println!(
+        "{} clients, {} byte payload, pipeline depth {}, keyspace {}",
+        options.clients, options.payload_size, options.pipeline, options.keyspace
+    );
+    println!("{}", green(&format!("{:.0} requests per second", completed as f64 / seconds)));
+    println!();
+    println!(
+        "{:<8} {:>10} {:>8} {:>12} {:>10} {:>10} {:>10} {:>10}",
+        "op", "requests", "errors", "ops/sec", "p50 ms", "p95 ms", "p99 ms", "max ms"
+    );
+
+    let mut all = Vec::with_capacity(completed);
+    for op in BenchOp::ALL {
+        let samples = &mut totals[op as usize];
+        if samples.latencies.is_empty() && samples.errors == 0 {
+            continue;
+        }
+        samples.latencies.sort_unstable();
+        print_bench_row(op.name(), &samples.latencies, samples.errors, seconds);
+        all.extend_from_slice(&samples.latencies);
+    }
+    all.sort_unstable();
+    print_bench_row("all", &all, errors, seconds);
+}
+
+// `latencies` must be sorted
+fn print_bench_row(name: &str, latencies: &[u64], errors: u64, seconds: f64) {
+    let millis = |micros: u64| micros as f64 / 1_000.0;
+    println!(
+        "{:<8} {:>10} {:>8} {:>12.0} {:>10.3} {:>10.3} {:>10.3} {:>10.3}",
+        name,
+        latencies.len(),
+        errors,
+        latencies.len() as f64 / seconds,
+        millis(percentile(latencies, 50.0)),
+        millis(percentile(latencies, 95.0)),
+        millis(percentile(latencies, 99.0)),
+        millis(latencies.last().copied().unwrap_or(0))
+    );
+}
+
+// Nearest-rank percentile of sorted samples
+fn percentile(sorted: &[u64], percent: f64) -> u64 {
+    if sorted.is_empty() {
+        return 0;
+    }
+    let rank = ((percent / 100.0) * sorted.len() as f64).ceil() as usize;
+    sorted[rank.clamp(1, sorted.len()) - 1]
+}
+
+fn pick_op(mix: &[(BenchOp, u32)], rng: &mut Rng) -> BenchOp {
+    let total: u64 = mix.iter().map(|(_, weight)| u64::from(*weight)).sum();
+    let mut roll = rng.next() % total;
+    for (op, weight) in mix {
+        if roll < u64::from(*weight) {
+            return *op;
+        }
+        roll -= u64::from(*weight);
+    }
+    mix[0].0
+}
+
+// xorshift64*, plenty for picking keys and operations
+struct Rng(u64);
+
+impl Rng {
+    fn new(seed: u64) -> Self {
+        let nanos = SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default().subsec_nanos();
+        Rng((seed.wrapping_add(1).wrapping_mul(0x9E37_79B9_7F4A_7C15) ^ u64::from(nanos)) | 1)
+    }
+
+    fn next(&mut self) -> u64 {
+        self.0 ^= self.0 >> 12;
+        self.0 ^= self.0 << 25;
+        self.0 ^= self.0 >> 27;
+        self.0.wrapping_mul(0x2545_F491_4F6C_DD1D)
+    }
+}
+
+// UTC as "YYYY-MM-DD HH:MM:SS.mmm"
+fn format_timestamp(time: SystemTime) -> String {
+    let elapsed = time.duration_since(UNIX_EPOCH).unwrap_or_default();
+    let seconds = elapsed.as_secs();
+    let (days, second_of_day) = ((seconds / 86_400) as i64, seconds % 86_400);
+
+    // Days since the epoch to a civil date, after Howard Hinnant's civil_from_days
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let day_of_era = z.rem_euclid(146_097);
+    let year_of_era = (day_of_era - day_of_era / 1_460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let month_index = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * month_index + 2) / 5 + 1;
+    let month = if month_index < 10 { month_index + 3 } else { month_index - 9 };
+    let year = year_of_era + era * 400 + if month <= 2 { 1 } else { 0 };
+
+    format!(
+        "{:04}-{:02}-{:02} {:02}:{:02}:{:02}.{:03}",
+        year,
+        month,
+        day,
+        second_of_day / 3_600,
+        second_of_day % 3_600 / 60,
+        second_of_day % 60,
+        elapsed.subsec_millis()
+    )
+}
+
+fn print_result(result: Result<Response, MginError>) {
+    let style: Option<fn(&str) -> String> = match &result {
+        Ok(Response::Status(_)) => Some(green),
+        Ok(Response::Null) => Some(yellow),
+        _ => None,
+    };
+    match describe(result) {
+        Ok(reply) => match style {
+            Some(style) => println!("{}", style(&reply)),
+            None => println!("{}", reply),
+        },
+        Err(message) => println!("{}", red(&message)),
+    }
+}
+
+// The printable form of a reply, or the failure message
+fn describe(result: Result<Response, MginError>) -> Result<String, String> {
+    match result {
+        Ok(Response::Ok(value)) => Ok(serde_json::to_string_pretty(&value).unwrap_or_else(|_| value.to_string())),
+        Ok(Response::Status(status)) => Ok(status),
+        Ok(Response::Count(count)) => Ok(format!("(integer) {}", count)),
+        Ok(Response::Null) => Ok("(nil)".to_string()),
+        Ok(Response::Error { message, .. }) | Err(MginError::ServerError { message, .. }) => {
+            Err(format!("ERROR: {}", message))
+        }
+        Err(e) => Err(e.to_string()),
+    }
+}
+
+fn history_path() -> Option<PathBuf> {
+    std::env::var_os("HOME").map(|home| PathBuf::from(home).join(".mgindb_cli_history"))
+}
+
+// Completes the command word at the start of the line
+struct CliHelper;
+
+impl Completer for CliHelper {
+    type Candidate = Pair;
+
+    fn complete(&self, line: &str, pos: usize, _ctx: &Context<'_>) -> rustyline::Result<(usize, Vec<Pair>)> {
+        let prefix = &line[..pos];
+        if prefix.contains(char::is_whitespace) {
+            return Ok((pos, Vec::new()));
+        }
+        let upper = prefix.to_ascii_uppercase();
+        let candidates = COMMANDS
+            .iter()
+            .filter(|command| command.starts_with(&upper))
+            .chain(SHELL_COMMANDS.iter().filter(|command| command.starts_with(&prefix.to_ascii_lowercase())))
+            .map(|command| Pair {
+                display: command.to_string(),
+                replacement: format!("{} ", command),
+            })
+            .collect();
+        Ok((0, candidates))
+    }
+}
+
+impl Hinter for CliHelper {
+    type Hint = String;
+}
+
+impl Highlighter for CliHelper {}
+
+impl Validator for CliHelper {}
+
+impl Helper for CliHelper {}
+
+fn color(text: &str, code: u8) -> String {
+    format!("\x1b[{}m{}\x1b[0m", code, text)
+}
+
+fn red(text: &str) -> String {
+    color(text, 31)
+}
+
+fn green(text: &str) -> String {
+    color(text, 32)
+}
+
+fn yellow(text: &str) -> String {
+    color(text, 33)
+}
+
+fn magenta(text: &str) -> String {
+    color(text, 35)
+}
+
+fn cyan(text: &str) -> String {
+    color(text, 36)
+}
@@ -0,0 +1,212 @@
+//! `#[derive(MginDocument)]` for the mgindb client, re-exported from `mgindb` with the `derive` feature.
+//!
+//! ```ignore
+//! #[derive(Serialize, Deserialize, MginDocument)]
+//! #[mgindb(prefix = "users")]
+//! struct User {
+//!     #[mgindb(id)]
+//!     id: u64,
+//!     #[mgindb(index)]
+//!     email: String,
+//!     #[mgindb(index = "set")]
+//!     tags: Vec<String>,
+//! }
+//! ```
+//!
+//! Without `prefix` the key prefix is the struct name in snake_case. Without `#[mgindb(id)]` a
+//! field named `id` is used. Index names are the Rust field names, so fields renamed with serde
+//! should not be indexed.
+
+use proc_macro::TokenStream;
+use proc_macro2::TokenStream as TokenStream2;
+use quote::quote;
+use syn::{parse_macro_input, Data, DeriveInput, Fields, Ident, LitStr};
+
+#[proc_macro_derive(MginDocument, attributes(mgindb))]
+pub fn derive_mgin_document(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    match expand(&input) {
+        Ok(tokens) => tokens.into(),
+        Err(e) => e.to_compile_error().into(),
+    }
+}
+
+fn expand(input: &DeriveInput) -> syn::Result<TokenStream2> {
+    let name = &input.ident;
+
+    let mut prefix = None;
+    for attr in input.attrs.iter().filter(|attr| attr.path().is_ident("mgindb")) {
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("prefix") {
+                let value: LitStr = meta.value()?.parse()?;
+                prefix = Some(value.value());
+                Ok(())
+            } else {
+                Err(meta.error("unknown mgindb attribute, expected `prefix`"))
+            }
+        })?;
+    }
+    let prefix = prefix.unwrap_or_else(|| snake_case(&name.to_string()));
+    if prefix.is_empty() || prefix.contains([':', ' ', '*']) {
+        return Err(syn::Error::new_spanned(name, "the key prefix must be non-empty without ':', ' ' or '*'"));
+    }
+
+    let fields = match &input.data {
+        Data::Struct(data) => match &data.fields {
+            Fields::Named(fields) => &fields.named,
+            _ => return Err(syn::Error::new_spanned(name, "MginDocument needs a struct with named fields")),
+        },
+        _ => return Err(syn::Error::new_spanned(name, "MginDocument can only be derived for structs")),
+    };
+
+    let mut id_field: Option<&Ident> = None;
+    let mut indexed = Vec::new();
+    for field in fields {
+        let ident = field.ident.as_ref().expect("named fields have identifiers");
+        for attr in field.attrs.iter().filter(|attr| attr.path().is_ident("mgindb")) {
+            attr.parse_nested_meta(|meta| {
+                if meta.path.is_ident("id") {
+                    if id_field.is_some() {
+                        return Err(meta.error("only one field can be #[mgindb(id)]"));
+                    }
+                    id_field = Some(ident);
+                    Ok(())
+                } else if meta.path.is_ident("index") {
+                    let index_type = if meta.input.peek(syn::Token![=]) {
+                        let value: LitStr = meta.value()?.parse()?;
+                        match value.value().as_str() {
+                            "string" => quote!(String),
+                            "set" => quote!(Set),
+                            _ => return Err(meta.error("index type must be \"string\" or \"set\"")),
+                        }
+                    } else {
+                        quote!(String)
+                    };
+                    let field_name = ident.to_string().trim_start_matches("r#").to_string();
+                    indexed.push(quote!((#field_name, ::mgindb::IndexType::#index_type)));
+                    Ok(())
+                } else {
+                    Err(meta.error("unknown mgindb attribute, expected `id` or `index`"))
+                }
+            })?;
+        }
+    }
+
+    let id_field = match id_field {
+        Some(ident) => ident,
+        None => fields
+            .iter()
+            .filter_map(|field| field.ident.as_ref())
+            .find(|ident| *ident == "id")
+            .ok_or_else(|| syn::Error::new_spanned(name, "no id field: add a field named `id` or mark one #[mgindb(id)]"))?,
+    };
+
+    let (impl_generics, type_generics, where_clause) = input.generics.split_for_impl();
+    Ok(quote! {
+        impl #impl_generics ::mgindb::MginDocument for #name #type_generics #where_clause {
+            const KEY_PREFIX: &'static str = #prefix;
+            const INDEXED_FIELDS: &'static [(&'static str, ::mgindb::IndexType)] = &[#(#indexed),*];
+
+            fn id(&self) -> ::std::string::String {
+                ::std::string::ToString::to_string(&self.#id_field)
+            }
+        }
+    })
+}
+
+// "UserProfile" -> "user_profile"
+fn snake_case(name: &str) -> String {
+    let mut snake = String::with_capacity(name.len() + 4);
+    for (i, c) in name.chars().enumerate() {
+        if c.is_uppercase() {
+            if i > 0 {
+                snake.push('_');
+            }
+            snake.extend(c.to_lowercase());
+        } else {
+            snake.push(c);
+        }
+    }
+    snake
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use syn::parse_quote;
+
+    fn error(input: DeriveInput) -> String {
+        match expand(&input) {
+            Ok(tokens) => panic!("expanded to {}", tokens),
+            Err(e) => e.to_string(),
+        }
+    }
+
+    #[test]
+    fn struct_names_become_snake_case_prefixes() {
+        assert_eq!(snake_case("User"), "user");
+        assert_eq!(snake_case("UserProfile"), "user_profile");
+        assert_eq!(snake_case("order"), "order");
+        assert_eq!(snake_case("Order2024Line"), "order2024_line");
+    }
+
+    #[test]
+    fn attributes_set_the_prefix_id_and_indices() {
+        let input: DeriveInput = parse_quote! {
+            #[mgindb(prefix = "users")]
+            struct User {
+                #[mgindb(id)]
+                uid: u64,
+                #[mgindb(index)]
+                email: String,
+                #[mgindb(index = "set")]
+                tags: Vec<String>,
+                r#type: String,
+            }
+        };
+        let expected = quote! {
+            impl ::mgindb::MginDocument for User {
+                const KEY_PREFIX: &'static str = "users";
+                const INDEXED_FIELDS: &'static [(&'static str, ::mgindb::IndexType)] =
+                    &[("email", ::mgindb::IndexType::String), ("tags", ::mgindb::IndexType::Set)];
+
+                fn id(&self) -> ::std::string::String {
+                    ::std::string::ToString::to_string(&self.uid)
+                }
+            }
+        };
+        assert_eq!(expand(&input).unwrap().to_string(), expected.to_string());
+    }
+
+    #[test]
+    fn defaults_to_the_snake_case_name_and_the_id_field() {
+        let input: DeriveInput = parse_quote! {
+            struct UserProfile<T: Clone> {
+                id: String,
+                #[mgindb(index)]
+                r#type: T,
+            }
+        };
+        let tokens = expand(&input).unwrap().to_string();
+        assert!(tokens.contains("impl < T : Clone > :: mgindb :: MginDocument for UserProfile < T >"), "{}", tokens);
+        assert!(tokens.contains("\"user_profile\""), "{}", tokens);
+        assert!(tokens.contains("(\"type\" , :: mgindb :: IndexType :: String)"), "{}", tokens);
+        assert!(tokens.contains("& self . id"), "{}", tokens);
+    }
+
+    #[test]
+    fn invalid_attributes_are_reported() {
+        assert!(error(parse_quote! { #[mgindb(table = "users")] struct User { id: u64 } })
+            .contains("expected `prefix`"));
+        assert!(error(parse_quote! { #[mgindb(prefix = "a:b")] struct User { id: u64 } })
+            .contains("the key prefix must be non-empty"));
+        assert!(error(parse_quote! { struct User { #[mgindb(key)] id: u64 } }).contains("expected `id` or `index`"));
+        assert!(error(parse_quote! { struct User { #[mgindb(index = "hash")] id: u64 } })
+            .contains("index type must be"));
+        assert!(error(parse_quote! { struct User { #[mgindb(id)] a: u64, #[mgindb(id)] b: u64 } })
+            .contains("only one field"));
+        assert!(error(parse_quote! { struct User { name: String } }).contains("no id field"));
+        assert!(error(parse_quote! { struct User(u64); }).contains("named fields"));
+        assert!(error(parse_quote! { enum User { A } }).contains("only be derived for structs"));
+    }
+}
@@ -0,0 +1,17 @@
+use mgindb::MginDBClient;
+
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let client = MginDBClient::connect("ws", "127.0.0.1", 6446, "your_username", "your_password").await?;
+
+    // Example usage
+    let response = client.set("myKey", "myValue").await?;
+    println!("Set Response: {}", response);
+
+    let response = client.query_raw("myKey", None, None).await?;
+    println!("Query Response: {}", response);
+
+    // Add more examples as needed...
+
+    Ok(())
+}
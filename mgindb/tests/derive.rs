@@ -0,0 +1,33 @@
+#![cfg(feature = "derive")]
+
+use mgindb::{IndexType, MginDocument};
+use serde::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize, MginDocument)]
+#[mgindb(prefix = "users")]
+struct User {
+    #[mgindb(id)]
+    uid: u64,
+    #[mgindb(index)]
+    email: String,
+    #[mgindb(index = "set")]
+    tags: Vec<String>,
+}
+
+#[derive(Serialize, Deserialize, MginDocument)]
+struct OrderLine {
+    id: String,
+}
+
+#[test]
+fn derived_documents_use_their_attributes() {
+    let user = User { uid: 7, email: "ada@example.com".to_string(), tags: Vec::new() };
+    assert_eq!(User::KEY_PREFIX, "users");
+    assert_eq!(User::INDEXED_FIELDS, &[("email", IndexType::String), ("tags", IndexType::Set)]);
+    assert_eq!(user.key(), "users:7");
+
+    let line = OrderLine { id: "a1".to_string() };
+    assert_eq!(OrderLine::KEY_PREFIX, "order_line");
+    assert!(OrderLine::INDEXED_FIELDS.is_empty());
+    assert_eq!(line.key(), "order_line:a1");
+}
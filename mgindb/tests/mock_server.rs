@@ -0,0 +1,239 @@
+use futures_util::StreamExt;
+use mgindb::testing::MockServer;
+use mgindb::{CommandLine, ImportOptions, KeyRules, MginError, NotificationOp, Op, ReconnectPolicy, Response};
+use serde_json::json;
+use std::future::Future;
+use std::time::Duration;
+
+/// Every test talks to an in-process server, so anything slower than this is a hang
+async fn within<T>(future: impl Future<Output = T>) -> T {
+    tokio::time::timeout(Duration::from_secs(10), future).await.expect("timed out")
+}
+
+fn server_error(result: mgindb::Result<impl std::fmt::Debug>) -> (String, String) {
+    match result {
+        Err(MginError::ServerError { code, message }) => (code, message),
+        other => panic!("expected a server error, got {:?}", other),
+    }
+}
+
+#[tokio::test]
+async fn replies_are_matched_to_requests_in_order() {
+    let server = MockServer::start().await.unwrap();
+    for i in 0..50 {
+        server.respond(&format!("QUERY key:{}", i), &format!("\"value {}\"", i));
+    }
+    let client = server.connect().await.unwrap();
+
+    let reads = (0..50).map(|i| {
+        let client = client.clone();
+        async move { (i, client.get(&format!("key:{}", i)).await.unwrap()) }
+    });
+    for (i, value) in within(futures_util::future::join_all(reads)).await {
+        assert_eq!(value, Some(format!("value {}", i)));
+    }
+}
+
+#[tokio::test]
+async fn pipelines_send_one_burst_and_keep_reply_order() {
+    let server = MockServer::start().await.unwrap();
+    server.respond("QUERY a", "1").respond("QUERY b", "ERROR: Key not found").respond("COUNT c", "7");
+    let client = server.connect().await.unwrap();
+    server.clear_received();
+
+    let responses = within(client.pipeline().set("a", "1").query("a").query("b").count("c").execute()).await.unwrap();
+    assert!(responses[0].is_ok());
+    assert_eq!(responses[1], Response::Count(1));
+    assert!(responses[2].is_error(), "a failed command is returned in place");
+    assert_eq!(responses[3], Response::Count(7));
+    assert_eq!(server.received(), vec!["SET a 1", "QUERY a", "QUERY b", "COUNT c"]);
+}
+
+#[tokio::test]
+async fn notifications_reach_matching_subscriptions_only() {
+    let server = MockServer::start().await.unwrap();
+    let client = server.connect().await.unwrap();
+    let mut user = within(client.subscribe("users:1")).await.unwrap();
+    let mut orders = within(client.psubscribe("orders:*")).await.unwrap();
+    assert!(server.received().contains(&"SUB users:1".to_string()));
+    assert!(server.received().contains(&"SUB orders:*".to_string()));
+
+    server.notify("products:1", json!({ "1": "ignored" }));
+    server.notify("orders:9", json!({ "9": { "total": 5 } }));
+    server.notify("users:1", json!({ "2": "other" }));
+
+    let order = within(orders.next()).await.unwrap();
+    assert_eq!((order.key.as_str(), order.op), ("orders:9", NotificationOp::Set));
+    assert_eq!(order.value, json!({ "total": 5 }));
+
+    // The container no longer holds the key, so it was deleted
+    let change = within(user.next()).await.unwrap();
+    assert_eq!((change.key.as_str(), change.op), ("users:1", NotificationOp::Delete));
+
+    assert!(tokio::time::timeout(Duration::from_millis(100), user.next()).await.is_err());
+    assert!(tokio::time::timeout(Duration::from_millis(100), orders.next()).await.is_err());
+}
+
+#[tokio::test]
+async fn server_errors_are_reported() {
+    let server = MockServer::start().await.unwrap();
+    server
+        .respond_prefix("SET bad", "ERROR: Invalid value")
+        .respond("QUERY session", "\"token\"")
+        .respond_prefix("SET session", "Scheduler is not active.")
+        .respond_prefix("INDICES CREATE", "ERROR: Index already exists")
+        .respond_prefix("SCHEDULE ADD", "ERROR: Invalid command")
+        .respond_prefix("INDICES FLUSH", "ERROR: Index not found");
+    let client = server.connect().await.unwrap();
+
+    assert_eq!(server_error(client.set("bad", "1").await), ("ERROR".to_string(), "Invalid value".to_string()));
+    let (code, _) = server_error(client.expire("session", Duration::from_secs(5)).await);
+    assert_eq!(code, "SCHEDULER_INACTIVE");
+    server_error(client.indices().create("users", "email").await);
+    server_error(client.indices().drop("users", "email").await);
+    server_error(client.scheduler().add("0 * * * *", CommandLine::new("DEL").key("users:tmp")).await);
+}
+
+#[tokio::test]
+async fn rejected_credentials_fail_the_connect() {
+    let server = MockServer::start().await.unwrap();
+    server.reject_auth("ERROR: Invalid credentials");
+    match within(server.connect()).await {
+        Err(MginError::AuthFailed(_)) => {}
+        other => panic!("expected AuthFailed, got {:?}", other.map(|_| ())),
+    }
+}
+
+#[tokio::test]
+async fn reconnects_and_restores_subscriptions() {
+    let server = MockServer::start().await.unwrap();
+    server.respond("QUERY greeting", "\"hello\"");
+    let policy = ReconnectPolicy::default().initial_delay(Duration::from_millis(10)).jitter(0.0);
+    let client = server.builder().reconnect_policy(policy).connect().await.unwrap();
+    let mut changes = within(client.subscribe("greeting")).await.unwrap();
+
+    server.disconnect_all();
+    // The subscription is renewed on the new connection
+    let subscribes = || server.received().iter().filter(|command| *command == "SUB greeting").count();
+    within(async {
+        while subscribes() < 2 {
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+    })
+    .await;
+    assert_eq!(within(client.get("greeting")).await.unwrap(), Some("hello".to_string()));
+    server.notify("greeting", json!({ "greeting": "hi" }));
+    assert_eq!(within(changes.next()).await.unwrap().value, json!("hi"));
+}
+
+#[tokio::test]
+async fn set_json_escapes_expiries_and_refuses_wrapped_values() {
+    let server = MockServer::start().await.unwrap();
+    let client = server.connect().await.unwrap();
+    server.clear_received();
+
+    let wrapped = within(client.set_json("item", &json!({ "value": 1, "unit": "kg" }))).await;
+    assert!(matches!(wrapped, Err(MginError::InvalidArgument(_))));
+    within(client.set_json("item", &json!({ "note": "EXPIRE soon" }))).await.unwrap();
+    assert_eq!(server.received(), vec![r#"SET item {"note":"\u0045XPIRE soon"}"#]);
+}
+
+#[tokio::test]
+async fn try_lock_takes_a_free_lock_and_backs_off_from_a_held_one() {
+    let server = MockServer::start().await.unwrap();
+    server.respond("QUERY mgindb_locks:jobs", "None");
+    let client = server.connect().await.unwrap();
+
+    let guard = within(client.try_lock("jobs", Duration::from_secs(30))).await.unwrap().expect("lock is free");
+    assert!(guard.is_held());
+    let set = server.received().into_iter().find(|command| command.starts_with("SET mgindb_locks:jobs ")).unwrap();
+    let token = set.split(' ').nth(2).unwrap();
+    assert!(token.starts_with("lock") && !token.contains('-'), "{}", token);
+    assert!(set.ends_with(" EXPIRE(30)"), "{}", set);
+    // The mock does not store the lock, so there is nothing to release
+    std::mem::forget(guard);
+
+    server.respond("QUERY mgindb_locks:jobs", "\"lockf00d\"");
+    server.clear_received();
+    assert!(within(client.try_lock("jobs", Duration::from_secs(30))).await.unwrap().is_none());
+    assert!(!server.received().iter().any(|command| command.starts_with("SET ")));
+}
+
+#[tokio::test]
+async fn queue_publish_allocates_an_id_and_stores_the_payload() {
+    let server = MockServer::start().await.unwrap();
+    server.respond("QUERY jobs:seq", "3");
+    let client = server.connect().await.unwrap();
+    server.clear_received();
+
+    let id = within(client.queue("jobs").publish(&json!({ "task": "email" }))).await.unwrap();
+    assert_eq!(id, 3);
+    let received = server.received();
+    assert_eq!(&received[..2], ["INCR jobs:seq 1", "QUERY jobs:seq"]);
+    assert!(received[2].starts_with("SET jobs:messages:3 base64:"), "{}", received[2]);
+}
+
+#[tokio::test]
+async fn queue_trim_deletes_what_every_group_is_done_with() {
+    let server = MockServer::start().await.unwrap();
+    let groups = json!({
+        "billing": { "cursor": 7, "pending": { "5": { "consumer": "a", "deadline": 0, "attempts": 1 } } },
+        "audit": { "cursor": 6 },
+    });
+    server
+        .respond("QUERY jobs:groups", &groups.to_string())
+        .respond("QUERY jobs:trimmed", "2")
+        .respond("DEL jobs:messages:3|jobs:messages:4", "OK\nOK");
+    let client = server.connect().await.unwrap();
+    server.clear_received();
+
+    assert_eq!(within(client.queue("jobs").trim()).await.unwrap(), 2);
+    assert_eq!(
+        server.received(),
+        vec!["QUERY jobs:groups", "QUERY jobs:trimmed", "DEL jobs:messages:3|jobs:messages:4", "SET jobs:trimmed 4"]
+    );
+}
+
+#[tokio::test]
+async fn csv_rows_become_documents() {
+    let server = MockServer::start().await.unwrap();
+    let client = server.connect().await.unwrap();
+    server.clear_received();
+
+    let csv = "id,name,city\n1,Ada,London\n2,\"Hopper, Grace\",\"New\nYork\"\n";
+    let options = ImportOptions { prefix: "people".to_string(), key_column: "id".to_string(), ..Default::default() };
+    assert_eq!(within(client.import_csv(csv.as_bytes(), &options)).await.unwrap(), 2);
+
+    let received = server.received();
+    assert_eq!(received.len(), 2, "{:?}", received);
+    let document = |command: &str| -> serde_json::Value {
+        serde_json::from_str(command.splitn(3, ' ').nth(2).unwrap()).unwrap()
+    };
+    assert!(received[0].starts_with("SET people:1 "));
+    assert_eq!(document(&received[0]), json!({ "name": "Ada", "city": "London" }));
+    assert!(received[1].starts_with("SET people:2 "));
+    assert_eq!(document(&received[1]), json!({ "name": "Hopper, Grace", "city": "New\nYork" }));
+}
+
+#[tokio::test]
+async fn refused_commands_never_reach_the_server() {
+    let server = MockServer::start().await.unwrap();
+    let rules = KeyRules { forbidden: vec!['*'], ..KeyRules::default() };
+    let client = server.builder().key_rules(rules).connect().await.unwrap();
+    server.clear_received();
+
+    assert!(matches!(client.set("key", "-fast").await, Err(MginError::InvalidArgument(_))));
+    assert!(matches!(client.set("two words", "1").await, Err(MginError::InvalidArgument(_))));
+    assert!(matches!(client.mset(&[("a", "1"), ("b", "x|y")]).await, Err(MginError::InvalidArgument(_))));
+    assert!(matches!(client.query("users*").send().await, Err(MginError::InvalidKey(_))));
+    assert!(matches!(
+        client.query("users").filter("name", Op::Eq, "a|b").send().await,
+        Err(MginError::InvalidArgument(_))
+    ));
+    assert!(matches!(client.count("users*").send().await, Err(MginError::InvalidKey(_))));
+    assert!(matches!(
+        client.scheduler().add("0 * * * *", CommandLine::new("DEL").key("users*")).await,
+        Err(MginError::InvalidKey(_))
+    ));
+    assert!(server.received().is_empty(), "sent {:?}", server.received());
+}